@@ -0,0 +1,99 @@
+// Auth session management: a logged-in user's bearer token lives in app
+// state for the lifetime of the process and is persisted across restarts in
+// the OS keychain/credential store, never in a plaintext file.
+
+use std::sync::Mutex;
+
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+const KEYCHAIN_SERVICE: &str = "kika-app";
+const KEYCHAIN_USER: &str = "auth-session";
+const SESSION_EXPIRED_EVENT: &str = "auth://session-expired";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub id: String,
+    pub email: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredSession {
+    token: String,
+    user: UserInfo,
+}
+
+/// Managed state holding the current session plus the single `reqwest`
+/// client every backend call should reuse.
+pub struct AuthSession {
+    session: Mutex<Option<StoredSession>>,
+    pub client: reqwest::Client,
+}
+
+impl AuthSession {
+    pub fn new() -> Self {
+        let session = keychain_entry()
+            .ok()
+            .and_then(|entry| entry.get_password().ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok());
+
+        Self {
+            session: Mutex::new(session),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn token(&self) -> Option<String> {
+        self.session.lock().unwrap().as_ref().map(|s| s.token.clone())
+    }
+
+    pub fn user(&self) -> Option<UserInfo> {
+        self.session.lock().unwrap().as_ref().map(|s| s.user.clone())
+    }
+
+    fn set(&self, session: StoredSession) -> Result<(), String> {
+        if let Ok(entry) = keychain_entry() {
+            let raw = serde_json::to_string(&session).map_err(|e| e.to_string())?;
+            entry.set_password(&raw).map_err(|e| e.to_string())?;
+        }
+        *self.session.lock().unwrap() = Some(session);
+        Ok(())
+    }
+
+    fn clear(&self) {
+        if let Ok(entry) = keychain_entry() {
+            let _ = entry.delete_password();
+        }
+        *self.session.lock().unwrap() = None;
+    }
+}
+
+fn keychain_entry() -> keyring::Result<Entry> {
+    Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_USER)
+}
+
+#[tauri::command]
+pub async fn login(
+    token: String,
+    user: UserInfo,
+    state: State<'_, AuthSession>,
+) -> Result<(), String> {
+    state.set(StoredSession { token, user })
+}
+
+#[tauri::command]
+pub async fn logout(state: State<'_, AuthSession>) -> Result<(), String> {
+    state.clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn is_logged_in(state: State<'_, AuthSession>) -> Result<Option<UserInfo>, String> {
+    Ok(state.user())
+}
+
+/// Emits the session-expired event so the frontend can prompt re-login.
+pub fn notify_session_expired(app: &AppHandle) {
+    let _ = app.emit_all(SESSION_EXPIRED_EVENT, ());
+}
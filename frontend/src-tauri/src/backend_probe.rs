@@ -0,0 +1,50 @@
+// Detects whether a core backend is already listening before we spawn a new
+// sidecar, so dev setups and restart-after-crash don't fight another process
+// over the same port.
+
+use std::time::Duration;
+
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+
+const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// True if something is already answering `{base_url}/healthz` successfully.
+async fn is_healthy(base_url: &str) -> bool {
+    let client = match reqwest::Client::builder()
+        .timeout(HEALTH_PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+
+    matches!(
+        client.get(format!("{}/healthz", base_url)).send().await,
+        Ok(r) if r.status().is_success()
+    )
+}
+
+/// True if the given port has a process already listening on it, even if it
+/// isn't answering HTTP yet (e.g. still booting).
+fn is_port_listening(port: u16) -> bool {
+    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+    let proto_flags = ProtocolFlags::TCP;
+
+    get_sockets_info(af_flags, proto_flags)
+        .map(|sockets| {
+            sockets.iter().any(|s| match &s.protocol_socket_info {
+                ProtocolSocketInfo::Tcp(tcp) => {
+                    tcp.local_port == port && tcp.state == TcpState::Listen
+                }
+                _ => false,
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// Combined check run before spawning: a healthy response wins immediately;
+/// otherwise fall back to a raw socket scan to catch a process that's merely
+/// slow to answer.
+pub async fn already_running(base_url: &str, port: u16) -> bool {
+    is_healthy(base_url).await || is_port_listening(port)
+}
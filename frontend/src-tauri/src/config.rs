@@ -0,0 +1,204 @@
+// Backend configuration: each backend (core, auth) is described by a URI
+// whose scheme selects how we treat it.
+//
+// - `sidecar://<binary-name>` - spawn and supervise locally. Only the core
+//   target is ever spawned, and every `sidecar://` URI resolves to the core
+//   sidecar's address (`http://127.0.0.1:8001`); an auth target given one is
+//   not started.
+// - `http://...` / `https://...` - remote, health-check only, never spawn.
+//
+// Loaded once at startup from a config file in the app data dir, overridable
+// by env vars, so users can point kika-app at a self-hosted core or auth
+// instance without rebuilding.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+const CONFIG_FILE_NAME: &str = "backends.json";
+const DEFAULT_CORE_SIDECAR_URL: &str = "http://127.0.0.1:8001";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RawConfig {
+    core: String,
+    auth: String,
+}
+
+impl Default for RawConfig {
+    fn default() -> Self {
+        Self {
+            core: "sidecar://kika-backend-core".to_string(),
+            auth: "https://kika-backend.onrender.com".to_string(),
+        }
+    }
+}
+
+/// A single backend as resolved from its configured URI.
+#[derive(Clone, Debug)]
+pub enum Backend {
+    /// `sidecar://<binary-name>` - we own the process lifecycle.
+    Sidecar { binary: String, url: String },
+    /// `http(s)://...` - someone else owns it, we only ever health-check it.
+    Remote { url: String },
+}
+
+impl Backend {
+    fn parse(uri: &str) -> Result<Self, String> {
+        if let Some(binary) = uri.strip_prefix("sidecar://") {
+            Ok(Backend::Sidecar {
+                binary: binary.to_string(),
+                url: DEFAULT_CORE_SIDECAR_URL.to_string(),
+            })
+        } else if uri.starts_with("http://") || uri.starts_with("https://") {
+            Ok(Backend::Remote {
+                url: uri.to_string(),
+            })
+        } else {
+            Err(format!("Unrecognized backend URI scheme: {}", uri))
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        match self {
+            Backend::Sidecar { url, .. } => url,
+            Backend::Remote { url } => url,
+        }
+    }
+
+    pub fn is_sidecar(&self) -> bool {
+        matches!(self, Backend::Sidecar { .. })
+    }
+}
+
+// Parses a backend URI, loudly falling back to `default` (and logging why)
+// instead of silently routing a misconfigured override back to a hardcoded
+// backend with no indication anything was wrong.
+fn parse_or_fallback(name: &str, uri: &str, default: Backend) -> Backend {
+    match Backend::parse(uri) {
+        Ok(backend) => backend,
+        Err(e) => {
+            log::warn!(
+                "Ignoring \"{}\" backend URI \"{}\": {}. Falling back to {}",
+                name,
+                uri,
+                e,
+                default.url()
+            );
+            default
+        }
+    }
+}
+
+/// Typed, named backend targets resolved at startup. Managed as app state so
+/// commands can look a target up by name instead of hardcoding a URL.
+pub struct BackendConfig {
+    targets: HashMap<String, Backend>,
+}
+
+impl BackendConfig {
+    /// Loads config from `backends.json` in the app data dir, falling back
+    /// to built-in defaults and writing the file out if it doesn't exist yet
+    /// so it's discoverable and editable. `KIKA_CORE_URL`/`KIKA_AUTH_URL`
+    /// env vars take precedence over both, for dev/CI overrides.
+    pub fn load(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join(CONFIG_FILE_NAME);
+        let file_config = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<RawConfig>(&raw).ok());
+
+        let raw = file_config.unwrap_or_else(|| {
+            let defaults = RawConfig::default();
+            if !path.exists() {
+                if let Ok(serialized) = serde_json::to_string_pretty(&defaults) {
+                    let _ = std::fs::create_dir_all(app_data_dir);
+                    let _ = std::fs::write(&path, serialized);
+                }
+            }
+            defaults
+        });
+
+        let core_uri = std::env::var("KIKA_CORE_URL").unwrap_or(raw.core);
+        let auth_uri = std::env::var("KIKA_AUTH_URL").unwrap_or(raw.auth);
+
+        let mut targets = HashMap::new();
+        targets.insert(
+            "core".to_string(),
+            parse_or_fallback(
+                "core",
+                &core_uri,
+                Backend::Remote {
+                    url: DEFAULT_CORE_SIDECAR_URL.to_string(),
+                },
+            ),
+        );
+        targets.insert(
+            "auth".to_string(),
+            parse_or_fallback(
+                "auth",
+                &auth_uri,
+                Backend::Remote {
+                    url: "https://kika-backend.onrender.com".to_string(),
+                },
+            ),
+        );
+
+        Self { targets }
+    }
+
+    pub fn target(&self, name: &str) -> Option<&Backend> {
+        self.targets.get(name)
+    }
+
+    pub fn core_url(&self) -> String {
+        self.target("core").map(|b| b.url().to_string()).unwrap_or_default()
+    }
+
+    pub fn auth_url(&self) -> String {
+        self.target("auth").map(|b| b.url().to_string()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sidecar_uri() {
+        let backend = Backend::parse("sidecar://kika-backend-core").unwrap();
+        assert!(backend.is_sidecar());
+        assert_eq!(backend.url(), DEFAULT_CORE_SIDECAR_URL);
+    }
+
+    #[test]
+    fn parses_remote_uri() {
+        let backend = Backend::parse("https://kika-backend.onrender.com").unwrap();
+        assert!(!backend.is_sidecar());
+        assert_eq!(backend.url(), "https://kika-backend.onrender.com");
+    }
+
+    #[test]
+    fn rejects_unknown_scheme() {
+        assert!(Backend::parse("ftp://kika-backend-core").is_err());
+        assert!(Backend::parse("kika-backend-core").is_err());
+    }
+
+    #[test]
+    fn parse_or_fallback_uses_default_on_bad_uri() {
+        let default = Backend::Remote {
+            url: DEFAULT_CORE_SIDECAR_URL.to_string(),
+        };
+        let backend = parse_or_fallback("core", "not-a-uri", default);
+        assert_eq!(backend.url(), DEFAULT_CORE_SIDECAR_URL);
+        assert!(!backend.is_sidecar());
+    }
+
+    #[test]
+    fn parse_or_fallback_keeps_valid_uri() {
+        let default = Backend::Remote {
+            url: DEFAULT_CORE_SIDECAR_URL.to_string(),
+        };
+        let backend = parse_or_fallback("core", "sidecar://kika-backend-core", default);
+        assert!(backend.is_sidecar());
+    }
+}
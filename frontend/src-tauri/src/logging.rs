@@ -0,0 +1,57 @@
+// Structured logging: a human-readable layer on the console (respecting
+// `RUST_LOG`) plus a rotating JSON layer written to the app data directory,
+// so users filing issues can attach a machine-readable log instead of a
+// screenshot.
+
+use std::path::{Path, PathBuf};
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+const MAX_LOG_FILES: usize = 5;
+
+/// Initializes the global tracing subscriber and bridges the existing
+/// `log`-crate macros (used throughout `main.rs`/`supervisor.rs`, and by
+/// Tauri's own dependencies) into it.
+///
+/// The returned `WorkerGuard` must be kept alive for the lifetime of the
+/// app - dropping it stops the background log writer.
+pub fn init(app_data_dir: &Path) -> WorkerGuard {
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("kika-app")
+        .filename_suffix("log.json")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(app_data_dir))
+        .expect("failed to set up rotating log file");
+
+    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(fmt::layer().with_target(false))
+        .with(fmt::layer().json().with_ansi(false).with_writer(non_blocking))
+        .init();
+
+    // `init()` above already installs the `log` bridge through
+    // tracing-subscriber's default `tracing-log` feature, so this call only
+    // takes effect when that feature is off; its "logger already set" error
+    // is ignored rather than aborting startup.
+    let _ = tracing_log::LogTracer::init();
+
+    guard
+}
+
+fn log_dir(app_data_dir: &Path) -> PathBuf {
+    let dir = app_data_dir.join("logs");
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// Directory the frontend can surface via "Open logs" / "Copy log path".
+pub fn log_path(app_data_dir: &Path) -> PathBuf {
+    log_dir(app_data_dir)
+}
@@ -6,106 +6,174 @@ use std::sync::Mutex;
 use tauri::api::process::{Command, CommandChild};
 use tauri::{Manager, State};
 
+mod auth;
+mod backend_probe;
+mod config;
+mod logging;
+mod single_instance;
+mod supervisor;
+mod updater;
+
 // Store backend process handles
 struct BackendProcesses {
     core: Mutex<Option<CommandChild>>,
+    supervision: supervisor::Supervision,
+    // True when the core backend was already running (or is configured as a
+    // remote target) and we adopted it instead of spawning our own -
+    // stop_backends/window-close must leave it alone in that case.
+    external: Mutex<bool>,
+    // Serializes the whole "is something already running? if not, spawn one"
+    // decision in `start_core_backend` (and the supervisor's own respawns)
+    // across an `.await`, which `core`/`external` being plain `std::sync::Mutex`
+    // can't do. Without it, two concurrent start attempts (or a start racing
+    // a supervisor respawn) can both pass the "nothing running yet" check and
+    // spawn duplicate sidecars.
+    start_lock: tokio::sync::Mutex<()>,
 }
 
-fn get_core_backend_url() -> String {
-    env::var("KIKA_CORE_URL").unwrap_or_else(|_| "http://127.0.0.1:8001".to_string())
+// Pulls the port out of a backend URL for structured logging, e.g.
+// "http://127.0.0.1:8001" -> Some(8001).
+fn backend_port(url: &str) -> Option<u16> {
+    url.rsplit(':').next()?.trim_end_matches('/').parse().ok()
 }
 
-fn get_auth_backend_url() -> String {
-    // Always use the cloud-hosted auth backend on Render
-    env::var("KIKA_AUTH_URL").unwrap_or_else(|_| "https://kika-backend.onrender.com".to_string())
+// Spawn the core backend sidecar, handing back its event stream so callers
+// can supervise it instead of discarding it.
+fn spawn_core_sidecar(
+    binary: &str,
+) -> Result<(tauri::async_runtime::Receiver<tauri::api::process::CommandEvent>, CommandChild), String>
+{
+    Command::new_sidecar(binary)
+        .map_err(|e| format!("Failed to create sidecar command: {}", e))?
+        .spawn()
+        .map_err(|e| format!("Failed to start core backend: {}", e))
 }
 
 // Start the core backend sidecar
 #[tauri::command]
-async fn start_core_backend(state: State<'_, BackendProcesses>) -> Result<String, String> {
-    let mut core_guard = state.core.lock().map_err(|e| e.to_string())?;
-    
-    if core_guard.is_some() {
+pub(crate) async fn start_core_backend(
+    app: tauri::AppHandle,
+    state: State<'_, BackendProcesses>,
+    config: State<'_, config::BackendConfig>,
+) -> Result<String, String> {
+    let core = config
+        .target("core")
+        .ok_or_else(|| "No \"core\" backend configured".to_string())?;
+
+    let (binary, url) = match core {
+        config::Backend::Remote { url } => {
+            *state.external.lock().map_err(|e| e.to_string())? = true;
+            return Ok(format!("Using remote core backend at {}", url));
+        }
+        config::Backend::Sidecar { binary, url } => (binary.clone(), url.clone()),
+    };
+
+    // Held across the whole check-then-spawn-then-insert sequence below so a
+    // concurrent call (or the supervisor respawning at the same moment) can't
+    // slip in between the "nothing running yet" check and the insert.
+    let _start_guard = state.start_lock.lock().await;
+
+    if state.core.lock().map_err(|e| e.to_string())?.is_some()
+        || *state.external.lock().map_err(|e| e.to_string())?
+    {
         return Ok("Core backend already running".to_string());
     }
-    
-    // Try to start sidecar (for bundled app)
-    let result = Command::new_sidecar("kika-backend-core")
-        .map_err(|e| format!("Failed to create sidecar command: {}", e))?
-        .spawn();
-    
-    match result {
-        Ok((mut _rx, child)) => {
-            *core_guard = Some(child);
+
+    let port = backend_port(&url).unwrap_or(8001);
+    if backend_probe::already_running(&url, port).await {
+        *state.external.lock().map_err(|e| e.to_string())? = true;
+        log::info!("Core backend already listening on port {}, adopting it", port);
+        return Ok("Adopted externally running core backend".to_string());
+    }
+
+    *state.supervision.stopping.lock().map_err(|e| e.to_string())? = false;
+
+    match spawn_core_sidecar(&binary) {
+        Ok((rx, child)) => {
+            *state.core.lock().map_err(|e| e.to_string())? = Some(child);
+            supervisor::watch(app, rx);
             Ok("Core backend started".to_string())
         }
         Err(e) => {
-            Err(format!("Failed to start core backend: {}. In development mode, start it manually.", e))
+            Err(format!("{}. In development mode, start it manually.", e))
         }
     }
 }
 
 // Stop backend processes
 #[tauri::command]
-async fn stop_backends(state: State<'_, BackendProcesses>) -> Result<String, String> {
+pub(crate) async fn stop_backends(state: State<'_, BackendProcesses>) -> Result<String, String> {
+    *state.supervision.stopping.lock().map_err(|e| e.to_string())? = true;
+
+    // Waits out any respawn the supervisor has in flight, so the child it
+    // inserts is the one killed below.
+    let _start_guard = state.start_lock.lock().await;
+
+    if *state.external.lock().map_err(|e| e.to_string())? {
+        // We never spawned this process - leave it running.
+        return Ok("Backends left running (externally managed)".to_string());
+    }
+
     let mut core_guard = state.core.lock().map_err(|e| e.to_string())?;
-    
+
     if let Some(child) = core_guard.take() {
         let _ = child.kill();
     }
-    
+
     Ok("Backends stopped".to_string())
 }
 
-// Check if core backend is healthy (local sidecar)
+// Check if core backend is healthy
 #[tauri::command]
-async fn check_core_health() -> Result<bool, String> {
-    let url = format!("{}/healthz", get_core_backend_url());
-    
+async fn check_core_health(config: State<'_, config::BackendConfig>) -> Result<bool, String> {
+    let url = format!("{}/healthz", config.core_url());
+
     match reqwest::get(&url).await {
         Ok(response) => Ok(response.status().is_success()),
         Err(_) => Ok(false),
     }
 }
 
-// Check if auth backend is healthy (cloud-hosted on Render)
+// Check if auth backend is healthy
 #[tauri::command]
-async fn check_auth_health() -> Result<bool, String> {
-    let url = format!("{}/healthz", get_auth_backend_url());
-    
+async fn check_auth_health(config: State<'_, config::BackendConfig>) -> Result<bool, String> {
+    let url = format!("{}/healthz", config.auth_url());
+
     match reqwest::get(&url).await {
         Ok(response) => Ok(response.status().is_success()),
         Err(_) => Ok(false),
     }
 }
 
-// Legacy: check backend health - maps to auth health
-#[tauri::command]
-async fn check_backend_health() -> Result<bool, String> {
-    check_auth_health().await
-}
-
-// Legacy: start auth backend - no-op since auth is cloud-hosted
-#[tauri::command]
-async fn start_auth_backend(_state: State<'_, BackendProcesses>) -> Result<String, String> {
-    Ok("Auth backend is cloud-hosted at Render".to_string())
-}
-
-// Call Python API endpoint
+// Call a named backend target's API endpoint (e.g. "core" or "auth")
 #[tauri::command]
 async fn call_backend_api(
+    app: tauri::AppHandle,
+    auth: State<'_, auth::AuthSession>,
+    config: State<'_, config::BackendConfig>,
+    target: String,
     endpoint: String,
     method: String,
     body: Option<serde_json::Value>,
 ) -> Result<serde_json::Value, String> {
-    let backend_url = get_auth_backend_url();
-    let url = format!("{}{}", backend_url, endpoint);
-    let client = reqwest::Client::new();
-    
+    let backend = config
+        .target(&target)
+        .ok_or_else(|| format!("Unknown backend target: {}", target))?;
+    let url = format!("{}{}", backend.url(), endpoint);
+    let client = &auth.client;
+    let token = auth.token();
+
+    let with_auth = |mut req: reqwest::RequestBuilder| {
+        if let Some(token) = &token {
+            req = req.bearer_auth(token);
+        }
+        req
+    };
+
     let response = match method.as_str() {
-        "GET" => client.get(&url).send().await,
+        "GET" => with_auth(client.get(&url)).send().await,
         "POST" => {
-            let req = client.post(&url);
+            let req = with_auth(client.post(&url));
             if let Some(data) = body {
                 req.json(&data).send().await
             } else {
@@ -114,9 +182,12 @@ async fn call_backend_api(
         }
         _ => return Err("Unsupported HTTP method".to_string()),
     };
-    
+
     match response {
         Ok(resp) => {
+            if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                auth::notify_session_expired(&app);
+            }
             match resp.json::<serde_json::Value>().await {
                 Ok(data) => Ok(data),
                 Err(e) => Err(format!("Failed to parse response: {}", e)),
@@ -141,51 +212,108 @@ fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+// Get the directory holding the rotating JSON log files, for "Open logs" /
+// "Copy log path" in the frontend.
+#[tauri::command]
+fn get_log_path(app: tauri::AppHandle) -> Result<String, String> {
+    let app_data_dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Could not resolve app data dir".to_string())?;
+    Ok(logging::log_path(&app_data_dir).to_string_lossy().to_string())
+}
+
 // Get sidecar status for debugging
 #[tauri::command]
-async fn get_sidecar_status(state: State<'_, BackendProcesses>) -> Result<String, String> {
+async fn get_sidecar_status(
+    state: State<'_, BackendProcesses>,
+    config: State<'_, config::BackendConfig>,
+) -> Result<String, String> {
     let core_guard = state.core.lock().map_err(|e| e.to_string())?;
     let core_running = core_guard.is_some();
-    
-    let health_url = format!("{}/healthz", get_core_backend_url());
+    drop(core_guard);
+
+    let health_url = format!("{}/healthz", config.core_url());
     let health_ok = match reqwest::get(&health_url).await {
         Ok(response) => response.status().is_success(),
         Err(_) => false,
     };
-    
+
+    let supervision_state = *state.supervision.state.lock().map_err(|e| e.to_string())?;
+    let restart_count = *state.supervision.restart_count.lock().map_err(|e| e.to_string())?;
+    let external = *state.external.lock().map_err(|e| e.to_string())?;
+
     Ok(format!(
-        "Core sidecar process: {}, Health check: {}",
+        "Core sidecar process: {}, Health check: {}, Supervision: {:?}, Restarts: {}, External: {}",
         if core_running { "running" } else { "not running" },
-        if health_ok { "ok" } else { "failed" }
+        if health_ok { "ok" } else { "failed" },
+        supervision_state,
+        restart_count,
+        external,
     ))
 }
 
 fn main() {
-    env_logger::init();
-    
+    let context = tauri::generate_context!();
+    let app_data_dir = tauri::api::path::app_data_dir(context.config()).expect("no app data dir");
+
+    // Kept alive for the whole process: dropping it stops the log writer.
+    let _log_guard = logging::init(&app_data_dir);
+
+    let backend_config = config::BackendConfig::load(&app_data_dir);
+
+    let show_rx = match single_instance::acquire(&app_data_dir) {
+        single_instance::Guard::Primary(rx) => rx,
+        single_instance::Guard::AlreadyRunning => {
+            log::info!("kika-app is already running, focusing existing window and exiting");
+            return;
+        }
+    };
+
     tauri::Builder::default()
         .manage(BackendProcesses {
             core: Mutex::new(None),
+            supervision: supervisor::Supervision::new(),
+            external: Mutex::new(false),
+            start_lock: tokio::sync::Mutex::new(()),
         })
+        .manage(auth::AuthSession::new())
+        .manage(backend_config)
         .invoke_handler(tauri::generate_handler![
-            check_backend_health,
             check_auth_health,
             check_core_health,
             call_backend_api,
             read_local_file,
-            start_auth_backend,
             start_core_backend,
             stop_backends,
             get_app_version,
             get_sidecar_status,
+            get_log_path,
+            auth::login,
+            auth::logout,
+            auth::is_logged_in,
+            updater::check_for_update,
+            updater::install_update,
         ])
-        .setup(|app| {
+        .setup(move |app| {
             #[cfg(debug_assertions)]
             {
                 let window = app.get_window("main").unwrap();
                 window.open_devtools();
             }
-            
+
+            // Focus/raise the main window whenever a second launch tries to
+            // start up and is turned away by the single-instance guard.
+            let app_handle = app.handle();
+            std::thread::spawn(move || {
+                while show_rx.recv().is_ok() {
+                    if let Some(window) = app_handle.get_window("main") {
+                        let _ = window.unminimize();
+                        let _ = window.set_focus();
+                    }
+                }
+            });
+
             // In production, try to start backends automatically
             #[cfg(not(debug_assertions))]
             {
@@ -199,45 +327,84 @@ fn main() {
                         log::warn!("Could not auto-start backends: {}", e);
                     }
                 });
+
+                // Auto-check for updates on startup unless the user opted into
+                // manual-only checks.
+                if env::var("KIKA_AUTO_CHECK_UPDATES").map(|v| v != "0").unwrap_or(true) {
+                    let app_handle = app.handle();
+                    tauri::async_runtime::spawn(updater::auto_check_on_startup(app_handle));
+                }
             }
-            
+
             Ok(())
         })
         .on_window_event(|event| {
             if let tauri::WindowEvent::CloseRequested { .. } = event.event() {
                 let state: State<BackendProcesses> = event.window().state();
 
-                // Kill core backend
-                match state.core.lock() {
-                    Ok(mut core) => {
-                        if let Some(child) = core.take() {
-                            let _ = child.kill();
+                // Tell the supervisor this is an intentional shutdown so it
+                // doesn't treat the kill below as a crash and respawn a new
+                // sidecar while the app is exiting.
+                if let Ok(mut stopping) = state.supervision.stopping.lock() {
+                    *stopping = true;
+                }
+
+                // Kill core backend, unless it's a pre-existing process we
+                // merely adopted and never spawned ourselves.
+                let is_external = state.external.lock().map(|g| *g).unwrap_or(false);
+                if !is_external {
+                    match state.core.lock() {
+                        Ok(mut core) => {
+                            if let Some(child) = core.take() {
+                                let _ = child.kill();
+                            }
                         }
-                    }
-                    Err(_) => {}
-                };
+                        Err(_) => {}
+                    };
+                }
+
+                // Remove the single-instance pid lock file. A socket left
+                // behind by a crash is cleared by the next launch's `acquire`.
+                let resolver = event.window().app_handle().path_resolver();
+                if let Some(dir) = resolver.app_data_dir() {
+                    single_instance::release(&dir);
+                }
             }
         })
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
 
 #[cfg(not(debug_assertions))]
 async fn start_sidecar_backends(app: &tauri::AppHandle) -> Result<(), String> {
-    use tauri::api::process::Command;
-
     log::info!("Attempting to start core backend sidecar...");
-    
+
+    let config: State<config::BackendConfig> = app.state();
+    let core = config
+        .target("core")
+        .ok_or_else(|| "No \"core\" backend configured".to_string())?;
+
+    let (binary, url) = match core {
+        config::Backend::Remote { url } => {
+            log::info!("Core backend is configured as remote at {}, not spawning", url);
+            let state: State<BackendProcesses> = app.state();
+            *state.external.lock().map_err(|e| e.to_string())? = true;
+            return Ok(());
+        }
+        config::Backend::Sidecar { binary, url } => (binary.clone(), url.clone()),
+    };
+
+    let port = backend_port(&url).unwrap_or(8001);
+    if backend_probe::already_running(&url, port).await {
+        log::info!("Core backend already listening on port {}, adopting it", port);
+        let state: State<BackendProcesses> = app.state();
+        *state.external.lock().map_err(|e| e.to_string())? = true;
+        return Ok(());
+    }
+
     // Start core backend only - auth is cloud-hosted on Render
-    let core_result = Command::new_sidecar("kika-backend-core")
-        .map_err(|e| {
-            log::error!("Failed to create sidecar command: {}", e);
-            e.to_string()
-        })?
-        .spawn();
-
-    match core_result {
-        Ok((_, child)) => {
+    match spawn_core_sidecar(&binary) {
+        Ok((rx, child)) => {
             log::info!("Core backend sidecar started successfully");
             let state: State<BackendProcesses> = app.state();
             match state.core.lock() {
@@ -248,29 +415,80 @@ async fn start_sidecar_backends(app: &tauri::AppHandle) -> Result<(), String> {
                     log::warn!("Failed to lock core state: {}", e);
                 }
             };
+            supervisor::watch(app.clone(), rx);
         }
         Err(e) => {
             log::error!("Failed to spawn core backend sidecar: {}", e);
-            return Err(format!("Failed to start core backend: {}", e));
+            return Err(e);
         }
     }
 
     // Give the backend a moment to start
     tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-    
+
     // Check if it's actually running
-    let health_url = format!("{}/healthz", get_core_backend_url());
+    let health_url = format!("{}/healthz", url);
+    let started = std::time::Instant::now();
+    let pid = {
+        let state: State<BackendProcesses> = app.state();
+        state
+            .core
+            .lock()
+            .ok()
+            .and_then(|c| c.as_ref().map(|child| child.pid()))
+            .unwrap_or(0)
+    };
+    let port = backend_port(&url).unwrap_or(0);
+
     match reqwest::get(&health_url).await {
         Ok(response) if response.status().is_success() => {
-            log::info!("Core backend is healthy and responding");
+            tracing::info!(
+                pid,
+                port,
+                attempt = 1,
+                latency_ms = started.elapsed().as_millis() as u64,
+                status = "ok",
+                "core backend startup health check"
+            );
         }
         Ok(response) => {
-            log::warn!("Core backend returned non-success status: {}", response.status());
+            tracing::warn!(
+                pid,
+                port,
+                attempt = 1,
+                latency_ms = started.elapsed().as_millis() as u64,
+                status = response.status().as_u16(),
+                "core backend returned non-success status"
+            );
         }
         Err(e) => {
-            log::warn!("Core backend health check failed: {}", e);
+            tracing::warn!(
+                pid,
+                port,
+                attempt = 1,
+                latency_ms = started.elapsed().as_millis() as u64,
+                status = "unreachable",
+                error = %e,
+                "core backend health check failed"
+            );
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_port_parses_trailing_port() {
+        assert_eq!(backend_port("http://127.0.0.1:8001"), Some(8001));
+        assert_eq!(backend_port("http://127.0.0.1:8001/"), Some(8001));
+    }
+
+    #[test]
+    fn backend_port_rejects_non_numeric_tail() {
+        assert_eq!(backend_port("https://kika-backend.onrender.com"), None);
+    }
+}
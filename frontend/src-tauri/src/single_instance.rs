@@ -0,0 +1,129 @@
+// Single-instance guard: makes sure only one kika-app process ever owns the
+// backend sidecars. The first process to start binds a local socket rooted
+// in the app data dir and writes its pid to a lock file next to it. Any
+// later instance fails to bind, sends that listener a "show" message
+// instead, and exits before it ever touches `BackendProcesses`.
+//
+// The socket is scoped to `app_data_dir` (rather than a single global port)
+// so two different installs/profiles sharing a machine - e.g. a dev build
+// and the installed one, or two OS users - never contend for the same
+// rendezvous point.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+
+use interprocess::local_socket::{LocalSocketListener, LocalSocketStream};
+
+const SHOW_MESSAGE: &[u8] = b"show";
+
+fn lock_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("kika-app.lock")
+}
+
+// Unix domain sockets are filesystem objects, so this one lives directly
+// under `app_data_dir`. Windows named pipes aren't filesystem-rooted, so we
+// derive a name from the same path instead - still scoped per app-data
+// location, just not literally a file inside it.
+#[cfg(unix)]
+fn socket_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("kika-app.sock")
+}
+
+#[cfg(unix)]
+fn socket_name(app_data_dir: &Path) -> String {
+    socket_path(app_data_dir).to_string_lossy().to_string()
+}
+
+#[cfg(unix)]
+fn cleanup_stale_socket(app_data_dir: &Path) {
+    let _ = std::fs::remove_file(socket_path(app_data_dir));
+}
+
+#[cfg(windows)]
+fn socket_name(app_data_dir: &Path) -> String {
+    format!("kika-app-{:x}", hash_path(app_data_dir))
+}
+
+#[cfg(windows)]
+fn cleanup_stale_socket(_app_data_dir: &Path) {
+    // Named pipes have no backing file to clean up - a failed bind here
+    // means another process genuinely holds the pipe.
+}
+
+#[cfg(windows)]
+fn hash_path(path: &Path) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// What the caller should do after trying to acquire single-instance ownership.
+pub enum Guard {
+    /// We are the only instance. Holds the signal receiver that fires every
+    /// time a later launch asks us to focus the window.
+    Primary(Receiver<()>),
+    /// Another instance is already running and has been asked to show itself.
+    AlreadyRunning,
+}
+
+/// Tries to become the single running instance of the app.
+///
+/// Must be called before `tauri::Builder::default()` so a second launch can
+/// bail out without ever spawning a second `kika-backend-core` sidecar.
+pub fn acquire(app_data_dir: &Path) -> Guard {
+    let _ = std::fs::create_dir_all(app_data_dir);
+    let name = socket_name(app_data_dir);
+
+    if let Some(guard) = try_become_primary(app_data_dir, &name) {
+        return guard;
+    }
+
+    // Bind failed - either a live instance already holds it, or it's a
+    // stale socket left behind by a crash. Try to notify first; only clean
+    // up and retry if nothing answered.
+    if notify_running_instance(&name) {
+        return Guard::AlreadyRunning;
+    }
+
+    cleanup_stale_socket(app_data_dir);
+    try_become_primary(app_data_dir, &name).unwrap_or(Guard::AlreadyRunning)
+}
+
+fn try_become_primary(app_data_dir: &Path, name: &str) -> Option<Guard> {
+    let listener = LocalSocketListener::bind(name).ok()?;
+
+    let _ = std::fs::write(lock_file_path(app_data_dir), std::process::id().to_string());
+
+    let (tx, rx) = channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut buf = [0u8; SHOW_MESSAGE.len()];
+            if stream.read_exact(&mut buf).is_ok() && buf == SHOW_MESSAGE {
+                let _ = tx.send(());
+            }
+        }
+    });
+
+    Some(Guard::Primary(rx))
+}
+
+// Returns whether a running instance actually answered, so the caller can
+// tell "someone's there" apart from "stale socket, nobody home".
+fn notify_running_instance(name: &str) -> bool {
+    match LocalSocketStream::connect(name) {
+        Ok(mut stream) => stream.write_all(SHOW_MESSAGE).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Removes the pid lock file. Called alongside the sidecar kill logic on
+/// `CloseRequested`. `acquire` never reads this file; a socket left behind by
+/// a crash is cleared there once nothing answers on it.
+pub fn release(app_data_dir: &Path) {
+    let _ = std::fs::remove_file(lock_file_path(app_data_dir));
+}
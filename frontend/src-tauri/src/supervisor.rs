@@ -0,0 +1,181 @@
+// Supervises the core backend sidecar: consumes the `CommandEvent` stream
+// that `start_core_backend`/`start_sidecar_backends` used to throw away, and
+// respawns the sidecar with capped exponential backoff if it terminates
+// unexpectedly while the app is still running.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::api::process::CommandEvent;
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{config, spawn_core_sidecar, BackendProcesses};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// `.lock().unwrap()` would take the whole supervision task down (silently,
+// since nothing observes a panicked `tauri::async_runtime::spawn` task) the
+// first time any lock holder panicked while holding one of these. Bail out
+// of `run()` instead, logging why, the same way the rest of the codebase
+// turns a poisoned lock into a reported error rather than a panic.
+macro_rules! lock_or_bail {
+    ($mutex:expr, $what:expr) => {
+        match $mutex.lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                log::error!(
+                    "core sidecar supervisor: {} mutex poisoned, stopping supervision",
+                    $what
+                );
+                return;
+            }
+        }
+    };
+}
+
+/// Supervision state surfaced through `get_sidecar_status`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SidecarState {
+    Running,
+    Restarting,
+    Failed,
+}
+
+/// Restart bookkeeping, stored alongside `BackendProcesses::core`.
+pub struct Supervision {
+    pub state: Mutex<SidecarState>,
+    pub restart_count: Mutex<u32>,
+    pub stopping: Mutex<bool>,
+}
+
+impl Supervision {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(SidecarState::Running),
+            restart_count: Mutex::new(0),
+            stopping: Mutex::new(false),
+        }
+    }
+}
+
+/// Takes ownership of a just-spawned sidecar's event stream and supervises
+/// it for the lifetime of the app.
+pub fn watch(app: AppHandle, rx: Receiver<CommandEvent>) {
+    tauri::async_runtime::spawn(run(app, rx));
+}
+
+async fn run(app: AppHandle, mut rx: Receiver<CommandEvent>) {
+    let mut backoff = INITIAL_BACKOFF;
+
+    let binary = {
+        let config: State<config::BackendConfig> = app.state();
+        match config.target("core") {
+            Some(config::Backend::Sidecar { binary, .. }) => binary.clone(),
+            _ => {
+                log::warn!("core sidecar supervisor started without a sidecar:// config; stopping");
+                return;
+            }
+        }
+    };
+
+    loop {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => log::info!(target: "kika-backend-core", "{}", line),
+                CommandEvent::Stderr(line) => log::warn!(target: "kika-backend-core", "{}", line),
+                CommandEvent::Error(err) => {
+                    log::error!(target: "kika-backend-core", "sidecar error: {}", err)
+                }
+                CommandEvent::Terminated(payload) => {
+                    log::warn!("core sidecar terminated unexpectedly: {:?}", payload);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        let state: State<BackendProcesses> = app.state();
+        if *lock_or_bail!(state.supervision.stopping, "stopping") {
+            *lock_or_bail!(state.core, "core") = None;
+            return;
+        }
+
+        *lock_or_bail!(state.supervision.state, "supervision state") = SidecarState::Restarting;
+        log::info!("restarting core sidecar in {:?}", backoff);
+        tokio::time::sleep(backoff).await;
+
+        // Shares `start_core_backend`'s lock so a respawn here can't race a
+        // manual start/stop call landing at the same moment.
+        let _start_guard = state.start_lock.lock().await;
+
+        // A stop or window close may have landed during the backoff sleep.
+        if *lock_or_bail!(state.supervision.stopping, "stopping") {
+            *lock_or_bail!(state.core, "core") = None;
+            return;
+        }
+
+        match spawn_core_sidecar(&binary) {
+            Ok((new_rx, child)) => {
+                *lock_or_bail!(state.core, "core") = Some(child);
+                *lock_or_bail!(state.supervision.restart_count, "restart count") += 1;
+
+                if wait_for_health(&app).await {
+                    *lock_or_bail!(state.supervision.state, "supervision state") = SidecarState::Running;
+                    backoff = INITIAL_BACKOFF;
+                } else {
+                    *lock_or_bail!(state.supervision.state, "supervision state") = SidecarState::Failed;
+                    backoff = next_backoff(backoff);
+                }
+
+                rx = new_rx;
+            }
+            Err(e) => {
+                // Don't sleep here too: the top of the loop already sleeps
+                // `backoff` before every respawn attempt (including this
+                // failure's), so an extra sleep in this arm was doubling the
+                // wait between attempts instead of following the schedule.
+                log::error!("failed to respawn core sidecar: {}", e);
+                *lock_or_bail!(state.supervision.state, "supervision state") = SidecarState::Failed;
+                backoff = next_backoff(backoff);
+            }
+        }
+    }
+}
+
+// Doubles the backoff, capped at `MAX_BACKOFF`.
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_backoff_doubles() {
+        assert_eq!(next_backoff(Duration::from_secs(1)), Duration::from_secs(2));
+        assert_eq!(next_backoff(Duration::from_secs(4)), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn next_backoff_caps_at_max() {
+        assert_eq!(next_backoff(MAX_BACKOFF), MAX_BACKOFF);
+        assert_eq!(next_backoff(Duration::from_secs(20)), MAX_BACKOFF);
+    }
+}
+
+async fn wait_for_health(app: &AppHandle) -> bool {
+    let config: State<config::BackendConfig> = app.state();
+    let url = format!("{}/healthz", config.core_url());
+    for _ in 0..5 {
+        if let Ok(resp) = reqwest::get(&url).await {
+            if resp.status().is_success() {
+                return true;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    false
+}
@@ -0,0 +1,114 @@
+// In-app update checking/installing, wired to Tauri's built-in updater.
+// Coordinates with the sidecar lifecycle so the bundled `kika-backend-core`
+// binary is replaced cleanly before the relaunch the updater triggers.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+const UPDATE_PROGRESS_EVENT: &str = "updater://progress";
+
+#[derive(Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub mandatory: bool,
+}
+
+// `tauri::updater::Update` doesn't expose a mandatory flag, so we infer one
+// the same way the update prompt should be read by the frontend: a major
+// version bump is mandatory, anything else (minor/patch) is optional.
+fn is_mandatory(current: &str, latest: &str) -> bool {
+    match (major_version(current), major_version(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+fn major_version(version: &str) -> Option<u64> {
+    version.split('.').next()?.parse().ok()
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum UpdateProgress {
+    Downloading { percent: Option<f64> },
+    ReadyToInstall,
+    Error { message: String },
+}
+
+fn emit_progress(app: &AppHandle, progress: UpdateProgress) {
+    let _ = app.emit_all(UPDATE_PROGRESS_EVENT, progress);
+}
+
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    match app.updater().check().await {
+        Ok(update) if update.is_update_available() => {
+            let version = update.latest_version().to_string();
+            let mandatory = is_mandatory(env!("CARGO_PKG_VERSION"), &version);
+            Ok(Some(UpdateInfo {
+                version,
+                notes: update.body().map(|b| b.to_string()),
+                mandatory,
+            }))
+        }
+        Ok(_) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn install_update(app: AppHandle) -> Result<(), String> {
+    let update = app.updater().check().await.map_err(|e| e.to_string())?;
+    if !update.is_update_available() {
+        return Ok(());
+    }
+
+    emit_progress(&app, UpdateProgress::Downloading { percent: None });
+
+    // Stop the sidecar first so the bundled binary can be replaced cleanly;
+    // `start_sidecar_backends` picks the new version back up on next launch.
+    let state: State<crate::BackendProcesses> = app.state();
+    let _ = crate::stop_backends(state).await;
+
+    match update.download_and_install().await {
+        Ok(()) => {
+            emit_progress(&app, UpdateProgress::ReadyToInstall);
+            // `download_and_install` only replaces the files on disk - it
+            // doesn't relaunch on its own, so without this the user keeps
+            // running the old binary (with no core backend, since we just
+            // stopped it above) until they close and reopen the app by hand.
+            tauri::api::process::restart(&app.env());
+        }
+        Err(e) => {
+            emit_progress(
+                &app,
+                UpdateProgress::Error {
+                    message: e.to_string(),
+                },
+            );
+
+            // The download/install failed, so there's no relaunch coming to
+            // pick the sidecar back up - bring it back ourselves instead of
+            // leaving the app without a core backend until a manual restart.
+            let state: State<crate::BackendProcesses> = app.state();
+            *state.supervision.stopping.lock().map_err(|e| e.to_string())? = false;
+            let config: State<crate::config::BackendConfig> = app.state();
+            if let Err(restart_err) = crate::start_core_backend(app.clone(), state, config).await {
+                log::error!("Failed to restart core backend after failed update: {}", restart_err);
+            }
+
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Checks for an update in the background and logs the outcome. Used for
+/// the "auto-check on startup" setting; errors never block app startup.
+pub async fn auto_check_on_startup(app: AppHandle) {
+    match check_for_update(app).await {
+        Ok(Some(info)) => log::info!("Update available: {}", info.version),
+        Ok(None) => log::info!("No update available"),
+        Err(e) => log::warn!("Update check failed: {}", e),
+    }
+}